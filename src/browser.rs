@@ -0,0 +1,183 @@
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Headers, Request, RequestInit, RequestMode, Response};
+
+pub mod worker;
+
+// console.log を呼び出すためのマクロ。
+// `web_sys::console::log_1` は JsValue しか受け取れないので、format! でまとめて渡す。
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        web_sys::console::log_1(&format!( $( $t )* ).into());
+    }
+}
+
+pub fn window() -> Result<web_sys::Window, JsValue> {
+    web_sys::window().ok_or_else(|| JsValue::from_str("No Window Found"))
+}
+
+pub fn document() -> Result<web_sys::Document, JsValue> {
+    window()?
+        .document()
+        .ok_or_else(|| JsValue::from_str("No Document Found"))
+}
+
+pub fn canvas() -> Result<web_sys::HtmlCanvasElement, JsValue> {
+    document()?
+        .get_element_by_id("canvas")
+        .ok_or_else(|| JsValue::from_str("No Canvas Element found with ID 'canvas'"))?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .map_err(|element| element.into())
+}
+
+pub fn context() -> Result<web_sys::CanvasRenderingContext2d, JsValue> {
+    canvas()?
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("No 2d context found"))?
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()
+        .map_err(|element| element.into())
+}
+
+pub fn spawn_local<F>(future: F)
+where
+    F: std::future::Future<Output = ()> + 'static,
+{
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+// GET/POST などのメソッド、ヘッダー、ボディを持った fetch リクエストを組み立てるための設定。
+// `RequestOptions::get()` がデフォルトの GET で、POST でスコアを送るときなどは
+// headers/body を自分で詰めて渡す。
+pub struct RequestOptions {
+    pub method: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Option<JsValue>,
+}
+
+impl RequestOptions {
+    pub fn get() -> Self {
+        RequestOptions {
+            method: "GET",
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+
+    pub fn post_json(body: &str) -> Self {
+        RequestOptions {
+            method: "POST",
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: Some(JsValue::from_str(body)),
+        }
+    }
+}
+
+// RequestInit/Headers/Request/Response の一連の流れを一箇所にまとめた、fetch の共通経路。
+// 2xx 以外のステータスはここでエラーに変換するので、呼び出し側は成功時の Response だけを見ればよい。
+async fn fetch(url: &str, options: &RequestOptions) -> Result<Response, JsValue> {
+    let init = RequestInit::new();
+    init.set_method(options.method);
+    init.set_mode(RequestMode::Cors);
+
+    if !options.headers.is_empty() {
+        let headers = Headers::new()?;
+        for (key, value) in &options.headers {
+            headers.set(key, value)?;
+        }
+        init.set_headers(&headers);
+    }
+
+    if let Some(body) = &options.body {
+        init.set_body(body);
+    }
+
+    let request = Request::new_with_str_and_init(url, &init)?;
+    let resp_value = JsFuture::from(window()?.fetch_with_request(&request)).await?;
+    let response: Response = resp_value.dyn_into()?;
+
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!(
+            "Request to {} failed with status {}",
+            url,
+            response.status()
+        )));
+    }
+
+    Ok(response)
+}
+
+pub async fn fetch_json<T: DeserializeOwned>(
+    url: &str,
+    options: &RequestOptions,
+) -> Result<T, JsValue> {
+    let json = JsFuture::from(fetch(url, options).await?.json()?).await?;
+    json.into_serde()
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+}
+
+pub async fn fetch_array_buffer(url: &str, options: &RequestOptions) -> Result<Vec<u8>, JsValue> {
+    let buffer = JsFuture::from(fetch(url, options).await?.array_buffer()?).await?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+// rAF のコールバックを登録する。戻り値はリクエスト ID で、キャンセルしたい場合に使う。
+pub fn request_animation_frame(callback: &Closure<dyn FnMut(f64)>) -> Result<i32, JsValue> {
+    window()?.request_animation_frame(callback.as_ref().unchecked_ref())
+}
+
+// 現在時刻(ミリ秒)を performance.now() から取得する。Date.now() よりも高精度で単調増加する。
+pub fn now() -> Result<f64, JsValue> {
+    Ok(window()?
+        .performance()
+        .ok_or_else(|| JsValue::from_str("Performance object not found"))?
+        .now())
+}
+
+pub type SharedKeyState = Rc<RefCell<KeyState>>;
+
+// キーボードの押下状態を保持する。`KeyboardEvent.code()` (例: "ArrowRight") をキーにする。
+#[derive(Default)]
+pub struct KeyState {
+    pressed_keys: HashMap<String, bool>,
+}
+
+impl KeyState {
+    pub fn is_pressed(&self, code: &str) -> bool {
+        *self.pressed_keys.get(code).unwrap_or(&false)
+    }
+
+    fn set_pressed(&mut self, code: String, pressed: bool) {
+        self.pressed_keys.insert(code, pressed);
+    }
+}
+
+// window に keydown/keyup のリスナーを登録し、共有の KeyState を返す。
+// ゲームループのクロージャはこの呼び出しのスタックフレームより長生きするので、
+// 状態は Rc<RefCell<...>> で共有し、リスナーのクロージャ自体は `forget()` して
+// JavaScript 側に解放の責任を委ねる。
+pub fn keyboard_event_listener() -> Result<SharedKeyState, JsValue> {
+    let key_state = Rc::new(RefCell::new(KeyState::default()));
+
+    let keydown_state = Rc::clone(&key_state);
+    let onkeydown = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        keydown_state.borrow_mut().set_pressed(event.code(), true);
+    }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+
+    let keyup_state = Rc::clone(&key_state);
+    let onkeyup = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        keyup_state.borrow_mut().set_pressed(event.code(), false);
+    }) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
+
+    let window = window()?;
+    window.add_event_listener_with_callback("keydown", onkeydown.as_ref().unchecked_ref())?;
+    window.add_event_listener_with_callback("keyup", onkeyup.as_ref().unchecked_ref())?;
+
+    onkeydown.forget();
+    onkeyup.forget();
+
+    Ok(key_state)
+}
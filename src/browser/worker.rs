@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use futures::channel::oneshot;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+type PendingJob = oneshot::Sender<Result<JsValue, JsValue>>;
+
+// 1 つの Web Worker と、その onmessage/onerror ハンドラ、処理待ちジョブの FIFO キューを
+// まとめたもの。ハンドラは Worker ごとに一度だけ登録し、以降のジョブはすべて同じハンドラが
+// 届いた順にキューの先頭へ結果を渡す。ハンドラを呼び出しのたびに張り替えると、前のジョブの
+// oneshot::Sender が行き場をなくして一生 resolve されなかったり、返信の取り違えが起きる。
+pub struct Worker {
+    worker: web_sys::Worker,
+    inflight: Rc<RefCell<VecDeque<PendingJob>>>,
+    // Worker に登録している間、クロージャを生かしておくために保持するだけで、
+    // これ自体を直接呼び出すことはない。
+    _onmessage: Closure<dyn FnMut(web_sys::MessageEvent)>,
+    _onerror: Closure<dyn FnMut(web_sys::ErrorEvent)>,
+}
+
+impl Worker {
+    pub fn new(script_url: &str) -> Result<Self, JsValue> {
+        let worker = web_sys::Worker::new(script_url)?;
+
+        // コンパイル済みの wasm Module と線形メモリを共有し、各 Worker が wasm を
+        // 自前で fetch/インスタンス化し直さなくて済むようにする。Worker 側のブートスクリプトは
+        // この最初のメッセージを `[module, memory]` として受け取り、
+        // `wasm_bindgen::init_memory(module, memory)` を呼んでから通常のジョブ処理に入る想定。
+        let init_message = js_sys::Array::of2(&wasm_bindgen::module(), &wasm_bindgen::memory());
+        worker.post_message(&init_message)?;
+
+        let inflight: Rc<RefCell<VecDeque<PendingJob>>> = Rc::new(RefCell::new(VecDeque::new()));
+
+        let onmessage_queue = Rc::clone(&inflight);
+        let onmessage = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+            if let Some(tx) = onmessage_queue.borrow_mut().pop_front() {
+                let _ = tx.send(Ok(event.data()));
+            }
+        }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+
+        let onerror_queue = Rc::clone(&inflight);
+        let onerror = Closure::wrap(Box::new(move |event: web_sys::ErrorEvent| {
+            if let Some(tx) = onerror_queue.borrow_mut().pop_front() {
+                let _ = tx.send(Err(event.into()));
+            }
+        }) as Box<dyn FnMut(web_sys::ErrorEvent)>);
+
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+
+        Ok(Worker {
+            worker,
+            inflight,
+            _onmessage: onmessage,
+            _onerror: onerror,
+        })
+    }
+
+    // ジョブを Worker へ投げ、結果を oneshot チャンネル越しに受け取る。`&self` なので
+    // 同じ Worker に対して複数のジョブを立て続けに投げられるが、返信は届いた順に
+    // キューの先頭から取り出すだけなので、取り違えたり送信し忘れたりしない。
+    pub async fn compute_async<In, Out>(&self, job: &In) -> Result<Out, JsValue>
+    where
+        In: Serialize,
+        Out: DeserializeOwned,
+    {
+        let message =
+            JsValue::from_serde(job).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+        let (tx, rx) = oneshot::channel();
+        self.inflight.borrow_mut().push_back(tx);
+        self.worker.post_message(&message)?;
+
+        let result = rx
+            .await
+            .map_err(|_| JsValue::from_str("Worker closed before responding"))??;
+
+        result
+            .into_serde()
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+}
+
+// 同じスクリプトを束にして複数の Worker を起動し、ラウンドロビンでジョブを割り振るだけの
+// 単純なプール。個々の Worker が自分のジョブキューを持っているので、プールの呼び出し側は
+// 1 体でもプールでも同じ API で済み、メインの rAF ループが重い計算でブロックされることはない。
+pub struct WorkerPool {
+    workers: Vec<Worker>,
+    next: RefCell<usize>,
+}
+
+impl WorkerPool {
+    pub fn new(script_url: &str, size: usize) -> Result<Self, JsValue> {
+        if size == 0 {
+            return Err(JsValue::from_str("WorkerPool size must be at least 1"));
+        }
+
+        let workers = (0..size)
+            .map(|_| Worker::new(script_url))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WorkerPool {
+            workers,
+            next: RefCell::new(0),
+        })
+    }
+
+    pub async fn compute_async<In, Out>(&self, job: &In) -> Result<Out, JsValue>
+    where
+        In: Serialize,
+        Out: DeserializeOwned,
+    {
+        let index = {
+            let mut next = self.next.borrow_mut();
+            let index = *next;
+            *next = (*next + 1) % self.workers.len();
+            index
+        };
+
+        self.workers[index].compute_async(job).await
+    }
+}
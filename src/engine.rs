@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use crate::browser;
+use crate::browser::KeyState;
+use crate::sprite::Rect;
+
+// 1フレームあたりの時間(ミリ秒)。60fps 相当の固定タイムステップ。
+const FRAME_SIZE: f32 = 1.0 / 60.0 * 1000.0;
+
+// CanvasRenderingContext2d への描画をまとめるだけの薄いラッパー。
+// ゲーム側は canvas や context を直接知らなくてよくなる。
+pub struct Renderer {
+    context: web_sys::CanvasRenderingContext2d,
+}
+
+impl Renderer {
+    pub fn new(context: web_sys::CanvasRenderingContext2d) -> Self {
+        Renderer { context }
+    }
+
+    pub fn clear(&self, width: f64, height: f64) {
+        self.context.clear_rect(0.0, 0.0, width, height);
+    }
+
+    pub fn draw_image(
+        &self,
+        image: &web_sys::HtmlImageElement,
+        frame: &Rect,
+        destination: (f64, f64),
+    ) {
+        self.context
+            .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                image,
+                frame.x.into(),
+                frame.y.into(),
+                frame.w.into(),
+                frame.h.into(),
+                destination.0,
+                destination.1,
+                frame.w.into(),
+                frame.h.into(),
+            )
+            .expect("Could not draw image");
+    }
+}
+
+// update と draw を分離したゲームの拡張点。ブートコードを触らずに新しいシーンを追加できる。
+pub trait Game {
+    fn update(&mut self, input: &KeyState, delta: f64);
+    fn draw(&self, renderer: &Renderer);
+}
+
+pub struct GameLoop;
+
+impl GameLoop {
+    // rAF の自己参照クロージャに乗せてゲームを駆動する。ゲーム本体は Rc<RefCell<...>> で
+    // 包み、毎フレームのクロージャから update は borrow_mut()、draw は borrow() して呼び出す。
+    pub fn start(game: impl Game + 'static) -> Result<(), JsValue> {
+        let renderer = Renderer::new(browser::context()?);
+        let key_state = browser::keyboard_event_listener()?;
+        let game = Rc::new(RefCell::new(game));
+
+        let f: Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>> = Rc::new(RefCell::new(None));
+        let g = f.clone();
+
+        let mut last_frame = browser::now()?;
+        let mut accumulated_delta = 0.0;
+
+        *g.borrow_mut() = Some(Closure::wrap(Box::new(move |perf_now: f64| {
+            accumulated_delta += (perf_now - last_frame) as f32;
+
+            while accumulated_delta >= FRAME_SIZE {
+                game.borrow_mut()
+                    .update(&key_state.borrow(), FRAME_SIZE as f64);
+                accumulated_delta -= FRAME_SIZE;
+            }
+            last_frame = perf_now;
+
+            game.borrow().draw(&renderer);
+
+            browser::request_animation_frame(f.borrow().as_ref().unwrap())
+                .expect("Could not request animation frame");
+        }) as Box<dyn FnMut(f64)>));
+
+        browser::request_animation_frame(
+            g.borrow()
+                .as_ref()
+                .expect("g.borrow() was None at the start of the game loop"),
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,3 @@
-use serde::Deserialize;
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Mutex;
 use wasm_bindgen::prelude::*;
@@ -7,24 +5,13 @@ use wasm_bindgen::JsCast;
 
 #[macro_use]
 mod browser;
+mod engine;
+mod sprite;
+mod walk_the_dog;
 
-// JSON のデシリアライズのターゲットとして Sheetを使えるようにする
-#[derive(Deserialize)]
-struct Sheet {
-    frames: HashMap<String, Cell>,
-}
-
-#[derive(Deserialize)]
-struct Rect {
-    x: u16,
-    y: u16,
-    w: u16,
-    h: u16,
-}
-#[derive(Deserialize)]
-struct Cell {
-    frame: Rect,
-}
+use engine::GameLoop;
+use sprite::Sheet;
+use walk_the_dog::WalkTheDog;
 
 // [重要]
 // JsValue は JavaScript から直接渡される値すべてを表す型だ。
@@ -39,26 +26,14 @@ struct Cell {
 pub fn main_js() -> Result<(), JsValue> {
     console_error_panic_hook::set_once();
 
-    let window = browser::window().expect("No Window Found");
-    let document = browser::document().expect("No Document Found");
-    let canvas: web_sys::HtmlCanvasElement = document
-        .get_element_by_id("canvas")
-        .unwrap()
-        .dyn_into::<web_sys::HtmlCanvasElement>() // get_element_by_id　で取得する Element を cast しないといけない。返り値が、Option<Element> のため
-        .unwrap();
-
-    let context = browser::context().expect("Could not get browser context");
-
     // spawn_localを呼び出す際には、引数として asyncの付いたブロックを渡す必要がある
     // このブロックに move を付けているのは、ブロック 内部で参照している変数束縛のすべての所有権をこのブロックに与えるためだ。
     // future の考え方。https://blog.tiqwab.com/2022/03/26/rust-future.html
     // トレイトが用意されていて、ランタイムはライブラリとして提供されているものを使うっていうのが面白い
     browser::spawn_local(async move {
-        let sheet: Sheet = browser::fetch_json("rhb.json")
+        let sheet: Sheet = browser::fetch_json("rhb.json", &browser::RequestOptions::get())
             .await
-            .expect("Could not fetch rhb.json")
-            .into_serde()
-            .expect("Could not convert rhb.json into a Sheet structure");
+            .expect("Could not fetch rhb.json");
 
         // Rust では let 文を使うと、その変数の以前のバージョンを隠して新しく束縛を作り直すので、変数名を変更する必要はない。
         let (success_tx, success_rx) = futures::channel::oneshot::channel::<Result<(), JsValue>>();
@@ -95,38 +70,8 @@ pub fn main_js() -> Result<(), JsValue> {
         image.set_src("rhb.png");
         success_rx.await;
 
-        let mut frame = -1;
-
-        // 繰り返し処理用のClosure. once ではいので何度も呼び出せる
-        let interval_callback = Closure::wrap(Box::new(move || {
-            frame = (frame + 1) % 8;
-            let frame_name = format!("Run ({}).png", frame + 1);
-
-            context.clear_rect(0.0, 0.0, 600.0, 600.0);
-            let sprite = sheet.frames.get(&frame_name).expect("Cell not found");
-            context.draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
-                &image,
-                sprite.frame.x.into(),
-                sprite.frame.y.into(),
-                sprite.frame.w.into(),
-                sprite.frame.h.into(),
-                300.0,
-                300.0,
-                sprite.frame.w.into(),
-                sprite.frame.h.into(),
-            );
-        }) as Box<dyn FnMut()>);
-
-        // 50ms ごとに interval_callback を呼び出す
-        browser::window()
-            .unwrap()
-            .set_interval_with_callback_and_timeout_and_arguments_0(
-                interval_callback.as_ref().unchecked_ref(),
-                50,
-            );
-
-        // このフューチャのスコープから 離れる際に Rust がクロージャを破棄しないようになる
-        interval_callback.forget();
+        let walk_the_dog = WalkTheDog::new(sheet, image);
+        GameLoop::start(walk_the_dog).expect("Could not start game loop");
     });
     Ok(())
 }
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::engine::Renderer;
+
+#[derive(Deserialize, Clone, Copy, Default)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+}
+
+#[derive(Deserialize)]
+pub struct Cell {
+    pub frame: Rect,
+    // texture-packer は `--trim` を付けずに書き出した場合 spriteSourceSize を出力しないことが
+    // あるので、欠けていてもシート全体のデシリアライズが失敗しないよう (0, 0) 起点にフォールバックする。
+    #[serde(rename = "spriteSourceSize", default)]
+    pub sprite_source_size: Rect,
+}
+
+// JSON のデシリアライズのターゲットとして Sheetを使えるようにする
+#[derive(Deserialize)]
+pub struct Sheet {
+    pub frames: HashMap<String, Cell>,
+}
+
+// texture-packer が出力する Sheet と、それが指すロード済み画像をまとめて持つラッパー。
+// `spriteSourceSize` を読んでいるので、トリムされたスプライトでも見た目の原点がずれない。
+pub struct SpriteSheet {
+    sheet: Sheet,
+    image: web_sys::HtmlImageElement,
+}
+
+impl SpriteSheet {
+    pub fn new(sheet: Sheet, image: web_sys::HtmlImageElement) -> Self {
+        SpriteSheet { sheet, image }
+    }
+
+    pub fn draw_frame(&self, renderer: &Renderer, frame_name: &str, dest_x: f64, dest_y: f64) {
+        let cell = self
+            .sheet
+            .frames
+            .get(frame_name)
+            .unwrap_or_else(|| panic!("Cell {} not found", frame_name));
+
+        let destination = (
+            dest_x + cell.sprite_source_size.x as f64,
+            dest_y + cell.sprite_source_size.y as f64,
+        );
+        renderer.draw_image(&self.image, &cell.frame, destination);
+    }
+}
+
+// 名前付きフレームの並びを、1 コマあたりの表示時間とループの有無とともに保持する。
+// "Run (1).png" のような frame_name を毎回組み立てる代わりに、経過時間から
+// 今どのフレームを見せるべきかを引ける。
+pub struct Animation {
+    frames: Vec<String>,
+    ms_per_frame: f64,
+    looping: bool,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<String>, ms_per_frame: f64, looping: bool) -> Self {
+        Animation {
+            frames,
+            ms_per_frame,
+            looping,
+        }
+    }
+
+    pub fn current_frame(&self, elapsed_ms: f64) -> &str {
+        let total_duration = self.ms_per_frame * self.frames.len() as f64;
+        let elapsed = if self.looping {
+            elapsed_ms.rem_euclid(total_duration)
+        } else {
+            elapsed_ms.min(total_duration - self.ms_per_frame)
+        };
+
+        let index = ((elapsed / self.ms_per_frame) as usize).min(self.frames.len() - 1);
+        &self.frames[index]
+    }
+}
@@ -0,0 +1,65 @@
+use crate::browser::KeyState;
+use crate::engine::{Game, Renderer};
+use crate::sprite::{Animation, Sheet, SpriteSheet};
+
+const RUN_FRAME_COUNT: u8 = 8;
+const IDLE_FRAME_COUNT: u8 = 10;
+const MS_PER_FRAME: f64 = 100.0;
+
+fn frame_names(prefix: &str, count: u8) -> Vec<String> {
+    (1..=count)
+        .map(|i| format!("{} ({}).png", prefix, i))
+        .collect()
+}
+
+// 赤帽の少年(Red Hat Boy)。RHB の "Run"/"Idle" アニメーションと左右移動だけを持つ、
+// 一番単純な Game 実装。
+pub struct WalkTheDog {
+    sprite_sheet: SpriteSheet,
+    run: Animation,
+    idle: Animation,
+    running: bool,
+    elapsed_ms: f64,
+    position_x: f64,
+}
+
+impl WalkTheDog {
+    pub fn new(sheet: Sheet, image: web_sys::HtmlImageElement) -> Self {
+        WalkTheDog {
+            sprite_sheet: SpriteSheet::new(sheet, image),
+            run: Animation::new(frame_names("Run", RUN_FRAME_COUNT), MS_PER_FRAME, true),
+            idle: Animation::new(frame_names("Idle", IDLE_FRAME_COUNT), MS_PER_FRAME, true),
+            running: false,
+            elapsed_ms: 0.0,
+            position_x: 300.0,
+        }
+    }
+}
+
+impl Game for WalkTheDog {
+    fn update(&mut self, input: &KeyState, delta: f64) {
+        let running = input.is_pressed("ArrowRight") || input.is_pressed("ArrowLeft");
+        if running != self.running {
+            // アニメーションの切り替え時は経過時間をリセットし、1 コマ目から再生し直す。
+            self.elapsed_ms = 0.0;
+        }
+        self.running = running;
+
+        if input.is_pressed("ArrowRight") {
+            self.position_x += 3.0;
+        } else if input.is_pressed("ArrowLeft") {
+            self.position_x -= 3.0;
+        }
+
+        self.elapsed_ms += delta;
+    }
+
+    fn draw(&self, renderer: &Renderer) {
+        renderer.clear(600.0, 600.0);
+
+        let animation = if self.running { &self.run } else { &self.idle };
+        let frame_name = animation.current_frame(self.elapsed_ms);
+        self.sprite_sheet
+            .draw_frame(renderer, frame_name, self.position_x, 300.0);
+    }
+}